@@ -3,11 +3,12 @@
 #![deny(missing_docs)]
 
 use std::{
+    ffi::CStr,
     fs::File,
     io,
     io::Write,
-    os::{fd::AsRawFd, unix::fs::PermissionsExt},
-    path::PathBuf,
+    os::{fd::AsRawFd, raw::c_char, unix::fs::PermissionsExt},
+    path::{Path, PathBuf},
     process::Command,
 };
 
@@ -60,8 +61,62 @@ fn glibc_detectors() -> Vec<(&'static str, &'static [u8])> {
     detectors
 }
 
+/// Attempts to resolve the weak `gnu_get_libc_version` symbol from the libc the current
+/// process is already linked against and call it directly.
+///
+/// This is by far the cheapest way to detect glibc: it requires no process spawn and no
+/// filesystem access. The symbol is only exported by glibc, so on musl (or any libc that
+/// doesn't define it) `dlsym` resolves to a null pointer and we simply report that glibc
+/// wasn't found, letting the caller fall back to the binary detectors.
+fn glibc_version_from_symbol() -> Option<(u32, u32, Option<u32>)> {
+    // SAFETY: `gnu_get_libc_version` takes no arguments and returns a pointer to a
+    // NUL-terminated string owned by libc. We never call through `sym` unless `dlsym`
+    // resolved it to a non-null address, which only happens when the symbol actually
+    // exists in the process (i.e. we're linked against glibc).
+    unsafe {
+        let sym = libc::dlsym(libc::RTLD_DEFAULT, c"gnu_get_libc_version".as_ptr());
+        if sym.is_null() {
+            tracing::debug!("gnu_get_libc_version symbol not found, not running on glibc");
+            return None;
+        }
+
+        let gnu_get_libc_version: extern "C" fn() -> *const c_char = std::mem::transmute(sym);
+        let version_ptr = gnu_get_libc_version();
+        if version_ptr.is_null() {
+            tracing::debug!("gnu_get_libc_version returned a null pointer");
+            return None;
+        }
+
+        let Ok(version) = CStr::from_ptr(version_ptr).to_str() else {
+            tracing::warn!("gnu_get_libc_version returned a non-UTF8 string");
+            return None;
+        };
+
+        let Some(parsed) = parse_version(version) else {
+            tracing::warn!("failed to parse glibc version '{version}' from gnu_get_libc_version");
+            return None;
+        };
+
+        Some(parsed)
+    }
+}
+
 /// Detect the current version of `glibc` using a binary detector.
+///
+/// The in-process `gnu_get_libc_version` symbol is tried first. Only when it is unavailable
+/// are the embedded binary detectors run, followed by `ldd --version` and finally the
+/// `libc.so.6` release banner (see [`glibc_version_from_image`]).
 pub fn glibc_version() -> Option<(u32, u32)> {
+    glibc_version_with_patch().map(|(major, minor, _patch)| (major, minor))
+}
+
+/// Same as [`glibc_version`] but also retains the patch component, when available, for
+/// callers that build a [`LibCVersion`].
+fn glibc_version_with_patch() -> Option<(u32, u32, Option<u32>)> {
+    if let Some(version) = glibc_version_from_symbol() {
+        return Some(version);
+    }
+
     for (arch, detector) in glibc_detectors() {
         // Create a temporary file for the detector.
         let mut f = match tempfile::tempfile() {
@@ -116,19 +171,218 @@ pub fn glibc_version() -> Option<(u32, u32)> {
             }
         };
 
-        let Some((major, minor)) = parse_major_minor_version(&stdout) else {
+        let Some(parsed) = parse_version(&stdout) else {
             tracing::warn!("failed to parse glibc version '{stdout}'");
             continue;
         };
 
-        return Some((major, minor));
+        return Some(parsed);
+    }
+
+    if let Some(version) = glibc_version_from_ldd() {
+        return Some(version);
+    }
+
+    if let Some(version) = glibc_version_from_image_with_patch() {
+        return Some(version);
+    }
+
+    None
+}
+
+/// The standard multiarch locations of `libc.so.6` on a Linux system, relative to the
+/// filesystem root so they can also be resolved under an arbitrary sysroot (see
+/// [`glibc_version_in_root`]).
+fn glibc_so_candidates() -> Vec<PathBuf> {
+    let mut candidates = vec![
+        PathBuf::from("lib64/libc.so.6"),
+        PathBuf::from("usr/lib/libc.so.6"),
+    ];
+
+    for triple in [
+        "x86_64-linux-gnu",
+        "i386-linux-gnu",
+        "aarch64-linux-gnu",
+        "arm-linux-gnueabihf",
+        "powerpc64le-linux-gnu",
+        "s390x-linux-gnu",
+    ] {
+        candidates.push(PathBuf::from(format!("lib/{triple}/libc.so.6")));
+    }
+
+    candidates
+}
+
+/// Detects the glibc version by reading `libc.so.6` directly and scanning its read-only data
+/// for the GNU release banner, without executing anything.
+///
+/// This works in sandboxes that forbid `exec`, and is also the basis for inspecting a
+/// foreign-arch rootfs (see [`glibc_version_in_root`]). The banner glibc embeds looks like:
+///
+/// ```text
+/// GNU C Library (Ubuntu GLIBC 2.39-0ubuntu8.5) stable release version 2.39.
+/// ```
+pub fn glibc_version_from_image() -> Option<(u32, u32)> {
+    glibc_version_from_image_with_patch().map(|(major, minor, _patch)| (major, minor))
+}
+
+/// Same as [`glibc_version_from_image`] but also retains the patch component, when available.
+fn glibc_version_from_image_with_patch() -> Option<(u32, u32, Option<u32>)> {
+    for candidate in glibc_so_candidates() {
+        let path = Path::new("/").join(candidate);
+        if !path.is_file() {
+            continue;
+        }
+
+        if let Some(version) = glibc_version_from_elf_path(&path) {
+            return Some(version);
+        }
+    }
+
+    None
+}
+
+/// Reads the ELF file at `path` and extracts the glibc release version from its banner.
+fn glibc_version_from_elf_path(path: &Path) -> Option<(u32, u32, Option<u32>)> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(err) => {
+            tracing::debug!("failed to read {}: {err}", path.display());
+            return None;
+        }
+    };
+
+    glibc_version_from_elf_bytes(&data)
+}
+
+/// Parses `data` as an ELF image and extracts the glibc release version from the `.rodata`
+/// section, restricting the string search to that section for robustness.
+fn glibc_version_from_elf_bytes(data: &[u8]) -> Option<(u32, u32, Option<u32>)> {
+    let elf = match goblin::elf::Elf::parse(data) {
+        Ok(elf) => elf,
+        Err(err) => {
+            tracing::debug!("failed to parse ELF image: {err}");
+            return None;
+        }
+    };
+
+    for section in &elf.section_headers {
+        if elf.shdr_strtab.get_at(section.sh_name) != Some(".rodata") {
+            continue;
+        }
+
+        let Some(rodata) = section_slice(data, section.sh_offset, section.sh_size) else {
+            continue;
+        };
+
+        if let Some(version) = extract_glibc_banner_version(rodata) {
+            return Some(version);
+        }
     }
 
     None
 }
 
+/// Slices `data` to the byte range described by a section header's `sh_offset`/`sh_size`,
+/// rejecting out-of-range or overflowing values instead of panicking.
+///
+/// `libc.so.6` is untrusted input pulled from arbitrary sysroots and OCI images (see
+/// [`glibc_version_in_root`]), and `goblin` doesn't validate `sh_offset`/`sh_size` for a
+/// plain `SHT_PROGBITS` section, so a corrupted or adversarial image must not be able to
+/// crash the calling process via an overflowing bounds check.
+fn section_slice(data: &[u8], offset: u64, size: u64) -> Option<&[u8]> {
+    let start = usize::try_from(offset).ok()?;
+    let size = usize::try_from(size).ok()?;
+    let end = start.checked_add(size)?;
+    data.get(start..end)
+}
+
+/// Searches `rodata` for the `"GNU C Library"` banner and extracts the version that follows
+/// `"release version "`, e.g. `2.39` out of `"...release version 2.39.\n"`.
+fn extract_glibc_banner_version(rodata: &[u8]) -> Option<(u32, u32, Option<u32>)> {
+    find_subslice(rodata, b"GNU C Library")?;
+
+    let marker = b"release version ";
+    let pos = find_subslice(rodata, marker)?;
+    let rest = &rodata[pos + marker.len()..];
+
+    let version_bytes: Vec<u8> = rest
+        .iter()
+        .copied()
+        .take_while(|b| b.is_ascii_digit() || *b == b'.')
+        .collect();
+
+    let version = std::str::from_utf8(&version_bytes).ok()?;
+    parse_version(version)
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`, if any.
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Falls back to `ldd --version` to detect glibc when none of the embedded detectors could
+/// run (e.g. an unusual architecture, `exec` is blocked, or the host binary is statically
+/// linked so no detector matches).
+///
+/// The first line of `ldd --version` on a glibc system looks like:
+///
+/// ```text
+/// ldd (Ubuntu GLIBC 2.35-0ubuntu3.8) 2.35
+/// ldd (GNU libc) 2.39
+/// ```
+///
+/// We take the whitespace-separated token immediately following the last `)` on that line.
+fn glibc_version_from_ldd() -> Option<(u32, u32, Option<u32>)> {
+    let output = match Command::new("ldd").arg("--version").output() {
+        Ok(output) => output,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            tracing::debug!("ldd is not present on this system");
+            return None;
+        }
+        Err(err) => {
+            tracing::debug!("failed to execute ldd --version: {err}");
+            return None;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some(first_line) = stdout.lines().next() else {
+        tracing::debug!("ldd --version produced no output on stdout");
+        return None;
+    };
+
+    let Some(parsed) = parse_ldd_version_line(first_line) else {
+        tracing::warn!("failed to parse glibc version from ldd --version output '{first_line}'");
+        return None;
+    };
+
+    Some(parsed)
+}
+
+/// Parses the glibc version out of the first line of `ldd --version`'s output, e.g.
+///
+/// ```text
+/// ldd (Ubuntu GLIBC 2.35-0ubuntu3.8) 2.35
+/// ldd (GNU libc) 2.39
+/// ```
+///
+/// by taking the whitespace-separated token immediately following the last `)` on the line.
+fn parse_ldd_version_line(first_line: &str) -> Option<(u32, u32, Option<u32>)> {
+    let after_paren = first_line.rsplit(')').next()?;
+    let version = after_paren.split_whitespace().next()?;
+    parse_version(version)
+}
+
 /// Detect the current version of `musl` `libc` by inspecting the `/lib/ld-musl-*.so.1` loaders.
 pub fn musl_libc_version() -> Option<(u32, u32)> {
+    musl_libc_version_with_patch().map(|(major, minor, _patch)| (major, minor))
+}
+
+/// Same as [`musl_libc_version`] but also retains the patch component, when available.
+fn musl_libc_version_with_patch() -> Option<(u32, u32, Option<u32>)> {
     for arch in ["x86_64", "aarch64", "i386", "armhf", "powerpc64le", "s390x"] {
         let loader = PathBuf::from(format!("/lib/ld-musl-{arch}.so.1"));
         if !loader.exists() {
@@ -144,17 +398,17 @@ pub fn musl_libc_version() -> Option<(u32, u32)> {
                 // Don't check output.status, because it's expected to return non-zero.
                 let output_text = String::from_utf8_lossy(&output.stderr);
 
-                // The output is in the form of "Version {major}.{minor}"
-                let Some((major, minor)) = output_text
+                // The output is in the form of "Version {major}.{minor}.{patch}"
+                let Some(parsed) = output_text
                     .lines()
                     .find_map(|l| l.strip_prefix("Version "))
-                    .and_then(parse_major_minor_version)
+                    .and_then(parse_version)
                 else {
                     tracing::debug!("failed to parse musl version from '{output_text}'");
                     continue;
                 };
 
-                return Some((major, minor));
+                return Some(parsed);
             }
         }
     }
@@ -162,12 +416,124 @@ pub fn musl_libc_version() -> Option<(u32, u32)> {
     None
 }
 
-/// Parses a version string into a major and minor version.
-fn parse_major_minor_version(version: &str) -> Option<(u32, u32)> {
+/// Returns `true` if `root` is the current process' own root filesystem, in which case it's
+/// safe to fall back to executing binaries found under it.
+fn root_is_host(root: &Path) -> bool {
+    root == Path::new("/")
+}
+
+/// Detects the version of glibc shipped under `root`, such as an OCI image, chroot, or
+/// cross-build sysroot.
+///
+/// When `root` is `/` this reuses the full [`glibc_version`] detection, including executing
+/// detectors, since that's the host we're actually running on anyway. For any other root we
+/// can't assume a detector built for the host architecture would even run, so we locate
+/// `libc.so.6` under `root` and read its release banner instead (see
+/// [`glibc_version_from_image`]).
+pub fn glibc_version_in_root(root: &Path) -> Option<(u32, u32)> {
+    glibc_version_in_root_with_patch(root).map(|(major, minor, _patch)| (major, minor))
+}
+
+/// Same as [`glibc_version_in_root`] but also retains the patch component, when available.
+fn glibc_version_in_root_with_patch(root: &Path) -> Option<(u32, u32, Option<u32>)> {
+    if root_is_host(root) {
+        if let Some(version) = glibc_version_with_patch() {
+            return Some(version);
+        }
+    }
+
+    for candidate in glibc_so_candidates() {
+        let path = root.join(candidate);
+        if !path.is_file() {
+            continue;
+        }
+
+        if let Some(version) = glibc_version_from_elf_path(&path) {
+            return Some(version);
+        }
+    }
+
+    None
+}
+
+/// Detects the version of musl shipped under `root`, such as an OCI image, chroot, or
+/// cross-build sysroot.
+///
+/// When `root` is `/` this reuses the full [`musl_libc_version`] detection, including
+/// executing the loader. For any other root we can't necessarily exec a foreign-arch loader,
+/// so instead we determine the version from the loader binary's embedded `"Version "` string
+/// (the same string it would otherwise print to stderr when invoked).
+pub fn musl_libc_version_in_root(root: &Path) -> Option<(u32, u32)> {
+    musl_libc_version_in_root_with_patch(root).map(|(major, minor, _patch)| (major, minor))
+}
+
+/// Same as [`musl_libc_version_in_root`] but also retains the patch component, when available.
+fn musl_libc_version_in_root_with_patch(root: &Path) -> Option<(u32, u32, Option<u32>)> {
+    if root_is_host(root) {
+        if let Some(version) = musl_libc_version_with_patch() {
+            return Some(version);
+        }
+    }
+
+    for arch in ["x86_64", "aarch64", "i386", "armhf", "powerpc64le", "s390x"] {
+        let loader = root.join(format!("lib/ld-musl-{arch}.so.1"));
+        if !loader.is_file() {
+            continue;
+        }
+
+        if let Some(version) = musl_version_from_loader_path(&loader) {
+            return Some(version);
+        }
+    }
+
+    None
+}
+
+/// Reads the musl version embedded in the loader binary at `path`, without executing it.
+fn musl_version_from_loader_path(path: &Path) -> Option<(u32, u32, Option<u32>)> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(err) => {
+            tracing::debug!("failed to read {}: {err}", path.display());
+            return None;
+        }
+    };
+
+    extract_musl_version(&data)
+}
+
+/// Searches `data` for musl's `"Version "` marker and extracts the version that follows it,
+/// e.g. `1.2.4` out of `"...Version 1.2.4\n"`.
+///
+/// Unlike [`extract_glibc_banner_version`], this searches the whole file instead of
+/// restricting itself to a single ELF section. That's deliberate, not an oversight: the musl
+/// loader is a small, statically linked binary with no large sections to produce a false
+/// positive in, and — unlike [`glibc_version_from_elf_bytes`] — nothing here does offset
+/// arithmetic on attacker-controlled values, so there's no overflow/crash risk in scanning
+/// the whole buffer even for an adversarial file.
+fn extract_musl_version(data: &[u8]) -> Option<(u32, u32, Option<u32>)> {
+    let marker = b"Version ";
+    let pos = find_subslice(data, marker)?;
+    let rest = &data[pos + marker.len()..];
+
+    let version_bytes: Vec<u8> = rest
+        .iter()
+        .copied()
+        .take_while(|b| b.is_ascii_digit() || *b == b'.')
+        .collect();
+
+    let version = std::str::from_utf8(&version_bytes).ok()?;
+    parse_version(version)
+}
+
+/// Parses a version string into its major, minor, and optional patch components, e.g.
+/// `"2.39"` into `(2, 39, None)` or musl's `"1.2.4"` into `(1, 2, Some(4))`.
+fn parse_version(version: &str) -> Option<(u32, u32, Option<u32>)> {
     let mut segment_iter = version.trim().split('.');
     let major = segment_iter.next()?.parse().ok()?;
     let minor = segment_iter.next()?.parse().ok()?;
-    Some((major, minor))
+    let patch = segment_iter.next().and_then(|segment| segment.parse().ok());
+    Some((major, minor, patch))
 }
 
 /// The family of libc implementation.
@@ -188,6 +554,11 @@ pub struct LibCVersion {
 
     /// The major and minor version of the library.
     pub version: (u32, u32),
+
+    /// The patch version of the library, if one could be determined. glibc releases are
+    /// usually just `major.minor` (e.g. `2.39`), while musl releases include a patch
+    /// component (e.g. `1.2.4`).
+    pub patch: Option<u32>,
 }
 
 /// Tries to detect the most likely version of libc on the current system.
@@ -197,23 +568,152 @@ pub struct LibCVersion {
 /// detect all libc implementations use the more specific functions (see [`glibc_version`] and
 /// [`musl_libc_version`]).
 pub fn libc_version() -> Option<LibCVersion> {
-    if let Some(version) = glibc_version() {
+    if let Some((major, minor, patch)) = glibc_version_with_patch() {
         return Some(LibCVersion {
             family: LibCFamily::GLibC,
-            version,
+            version: (major, minor),
+            patch,
         });
     }
 
-    if let Some(version) = musl_libc_version() {
+    if let Some((major, minor, patch)) = musl_libc_version_with_patch() {
         return Some(LibCVersion {
             family: LibCFamily::Musl,
-            version,
+            version: (major, minor),
+            patch,
         });
     }
 
     None
 }
 
+/// Detects every libc implementation present on the current system, rather than just the
+/// most likely one.
+///
+/// Useful on systems that can genuinely ship more than one libc, such as Alpine with
+/// `gcompat` installed, or multi-runtime container images, where callers may want to make
+/// their own policy decision instead of relying on the glibc-wins heuristic baked into
+/// [`libc_version`].
+pub fn all_libc_versions() -> Vec<LibCVersion> {
+    let mut versions = Vec::new();
+
+    if let Some((major, minor, patch)) = glibc_version_with_patch() {
+        versions.push(LibCVersion {
+            family: LibCFamily::GLibC,
+            version: (major, minor),
+            patch,
+        });
+    }
+
+    if let Some((major, minor, patch)) = musl_libc_version_with_patch() {
+        versions.push(LibCVersion {
+            family: LibCFamily::Musl,
+            version: (major, minor),
+            patch,
+        });
+    }
+
+    versions
+}
+
+/// Tries to detect the most likely version of libc shipped under `root`, such as an OCI
+/// image, chroot, or cross-build sysroot.
+///
+/// Follows the same glibc-wins heuristic as [`libc_version`] when multiple implementations
+/// are found. See [`glibc_version_in_root`] and [`musl_libc_version_in_root`] for the
+/// per-family detection.
+pub fn libc_version_in_root(root: &Path) -> Option<LibCVersion> {
+    if let Some((major, minor, patch)) = glibc_version_in_root_with_patch(root) {
+        return Some(LibCVersion {
+            family: LibCFamily::GLibC,
+            version: (major, minor),
+            patch,
+        });
+    }
+
+    if let Some((major, minor, patch)) = musl_libc_version_in_root_with_patch(root) {
+        return Some(LibCVersion {
+            family: LibCFamily::Musl,
+            version: (major, minor),
+            patch,
+        });
+    }
+
+    None
+}
+
+/// Emits a `cargo:rustc-cfg` line for each entry in `thresholds` that `version` satisfies,
+/// along with a matching `cargo:rustc-check-cfg` declaration for every entry so `--cfg`-gated
+/// code doesn't trigger `unexpected_cfgs` warnings even when the threshold isn't met.
+///
+/// Intended to be called from a `build.rs`. Each entry in `thresholds` is `(cfg_name, family,
+/// (major, minor))`, e.g. `("glibc_2_28", LibCFamily::GLibC, (2, 28))`; the flag is emitted
+/// when the detected libc is of that `family` and its version is greater than or equal to
+/// `(major, minor)`.
+pub fn emit_cfgs_for(version: &LibCVersion, thresholds: &[(&str, LibCFamily, (u32, u32))]) {
+    for line in cfg_lines_for(version, thresholds) {
+        println!("{line}");
+    }
+}
+
+/// Computes the `cargo:` lines [`emit_cfgs_for`] would print for `version` and `thresholds`,
+/// without actually printing them, so the logic can be tested in isolation.
+fn cfg_lines_for(version: &LibCVersion, thresholds: &[(&str, LibCFamily, (u32, u32))]) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for (name, family, at_least) in thresholds {
+        lines.push(format!("cargo:rustc-check-cfg=cfg({name})"));
+
+        if version.family == *family && version.version >= *at_least {
+            lines.push(format!("cargo:rustc-cfg={name}"));
+        }
+    }
+
+    lines
+}
+
+/// Detects the host's libc and emits the corresponding `cargo:rustc-cfg` lines, for use from
+/// a `build.rs`.
+///
+/// Emits:
+/// - `cfg(libc_family = "glibc")` or `cfg(libc_family = "musl")`, if a libc was detected
+/// - `cfg(glibc_minor = "NN")` / `cfg(musl_minor = "NN")` with the detected minor version
+/// - a handful of broadly useful glibc threshold flags via [`emit_cfgs_for`] (e.g.
+///   `cfg(glibc_2_28)`, relevant to `renameat2` availability)
+///
+/// Crates that need their own named thresholds should call [`libc_version`] and
+/// [`emit_cfgs_for`] directly instead.
+pub fn emit_libc_cfgs() {
+    println!(r#"cargo:rustc-check-cfg=cfg(libc_family, values("glibc", "musl"))"#);
+
+    let Some(version) = libc_version() else {
+        return;
+    };
+
+    let family = match version.family {
+        LibCFamily::GLibC => "glibc",
+        LibCFamily::Musl => "musl",
+    };
+    println!("cargo:rustc-cfg=libc_family=\"{family}\"");
+
+    let minor_cfg = match version.family {
+        LibCFamily::GLibC => "glibc_minor",
+        LibCFamily::Musl => "musl_minor",
+    };
+    println!("cargo:rustc-check-cfg=cfg({minor_cfg}, values(any()))");
+    println!("cargo:rustc-cfg={minor_cfg}=\"{}\"", version.version.1);
+
+    emit_cfgs_for(
+        &version,
+        &[
+            ("glibc_2_17", LibCFamily::GLibC, (2, 17)),
+            ("glibc_2_25", LibCFamily::GLibC, (2, 25)),
+            ("glibc_2_28", LibCFamily::GLibC, (2, 28)),
+            ("glibc_2_34", LibCFamily::GLibC, (2, 34)),
+        ],
+    );
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -236,4 +736,157 @@ mod test {
             None => panic!("no libc version detected"),
         }
     }
+
+    #[test]
+    fn test_section_slice_rejects_overflowing_bounds() {
+        let data = [0u8; 16];
+        assert_eq!(section_slice(&data, 0, 4), Some(&data[0..4]));
+        assert_eq!(section_slice(&data, u64::MAX, 1), None);
+        assert_eq!(section_slice(&data, 1, u64::MAX), None);
+        assert_eq!(section_slice(&data, 8, 16), None);
+    }
+
+    #[test]
+    fn test_extract_glibc_banner_version() {
+        let rodata = b"garbage\0GNU C Library (Ubuntu GLIBC 2.39-0ubuntu8.5) stable release version 2.39.\n";
+        assert_eq!(
+            extract_glibc_banner_version(rodata),
+            Some((2, 39, None))
+        );
+    }
+
+    #[test]
+    fn test_extract_glibc_banner_version_requires_gnu_banner() {
+        let rodata = b"release version 2.39.\n";
+        assert_eq!(extract_glibc_banner_version(rodata), None);
+    }
+
+    #[test]
+    fn test_parse_ldd_version_line() {
+        assert_eq!(
+            parse_ldd_version_line("ldd (Ubuntu GLIBC 2.35-0ubuntu3.8) 2.35"),
+            Some((2, 35, None))
+        );
+        assert_eq!(
+            parse_ldd_version_line("ldd (GNU libc) 2.39"),
+            Some((2, 39, None))
+        );
+        assert_eq!(parse_ldd_version_line("not ldd output at all"), None);
+    }
+
+    #[test]
+    fn test_cfg_lines_for() {
+        let version = LibCVersion {
+            family: LibCFamily::GLibC,
+            version: (2, 28),
+            patch: None,
+        };
+        let thresholds = [
+            ("glibc_2_17", LibCFamily::GLibC, (2, 17)),
+            ("glibc_2_28", LibCFamily::GLibC, (2, 28)),
+            ("glibc_2_34", LibCFamily::GLibC, (2, 34)),
+            ("musl_1_2", LibCFamily::Musl, (1, 2)),
+        ];
+
+        assert_eq!(
+            cfg_lines_for(&version, &thresholds),
+            vec![
+                "cargo:rustc-check-cfg=cfg(glibc_2_17)",
+                "cargo:rustc-cfg=glibc_2_17",
+                "cargo:rustc-check-cfg=cfg(glibc_2_28)",
+                "cargo:rustc-cfg=glibc_2_28",
+                "cargo:rustc-check-cfg=cfg(glibc_2_34)",
+                "cargo:rustc-check-cfg=cfg(musl_1_2)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("2.39"), Some((2, 39, None)));
+        assert_eq!(parse_version("1.2.4"), Some((1, 2, Some(4))));
+        assert_eq!(parse_version(" 2.35-0ubuntu3.8".trim()), None);
+        assert_eq!(parse_version("2"), None);
+        assert_eq!(parse_version("not a version"), None);
+    }
+
+    #[test]
+    fn test_find_subslice() {
+        assert_eq!(find_subslice(b"hello world", b"world"), Some(6));
+        assert_eq!(find_subslice(b"hello world", b"nope"), None);
+    }
+
+    #[test]
+    fn test_extract_musl_version() {
+        let data = b"musl libc (x86_64)\nVersion 1.2.4\nUsage: ...";
+        assert_eq!(extract_musl_version(data), Some((1, 2, Some(4))));
+    }
+
+    #[test]
+    fn test_extract_musl_version_missing_marker() {
+        assert_eq!(extract_musl_version(b"not a musl loader"), None);
+    }
+
+    /// Creates a unique, empty directory under the system temp dir for a test fixture, and
+    /// returns a guard that removes it on drop.
+    fn temp_fixture_dir(name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!(
+            "libc-detector-test-{name}-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&path).expect("failed to create fixture dir");
+        TempDir(path)
+    }
+
+    struct TempDir(PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_glibc_version_in_root_reads_fixture_libc() {
+        let real_libc = PathBuf::from("/lib/x86_64-linux-gnu/libc.so.6");
+        if !real_libc.is_file() {
+            eprintln!("no libc.so.6 found on this host, skipping");
+            return;
+        }
+
+        let fixture = temp_fixture_dir("glibc-root");
+        let lib_dir = fixture.0.join("lib/x86_64-linux-gnu");
+        std::fs::create_dir_all(&lib_dir).unwrap();
+        std::fs::copy(&real_libc, lib_dir.join("libc.so.6")).unwrap();
+
+        let version = glibc_version_in_root(&fixture.0);
+        assert!(
+            version.is_some(),
+            "expected a glibc version to be detected from the fixture root"
+        );
+    }
+
+    #[test]
+    fn test_musl_libc_version_in_root_reads_fixture_loader() {
+        let fixture = temp_fixture_dir("musl-root");
+        let lib_dir = fixture.0.join("lib");
+        std::fs::create_dir_all(&lib_dir).unwrap();
+        std::fs::write(
+            lib_dir.join("ld-musl-x86_64.so.1"),
+            b"musl libc (x86_64)\nVersion 1.2.4\nUsage: ...",
+        )
+        .unwrap();
+
+        assert_eq!(
+            musl_libc_version_in_root(&fixture.0),
+            Some((1, 2))
+        );
+    }
+
+    #[test]
+    fn test_libc_version_in_root_returns_none_for_empty_root() {
+        let fixture = temp_fixture_dir("empty-root");
+        assert_eq!(libc_version_in_root(&fixture.0), None);
+    }
 }